@@ -1,23 +1,141 @@
-use std::{process::Command, time::Instant};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    path::PathBuf,
+    process::{Command, Stdio},
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
 
-use anyhow::{anyhow, Context, Result};
+use async_channel::Sender;
 use directories_next::ProjectDirs;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tinybit::{widgets::Text, Color};
-use tinybit::{ScreenPos, Viewport};
+use tinybit::{ScreenPos, Size, Viewport};
 
 use crate::MeterTheme;
 
-pub fn load() -> Result<Conf> {
-    let config_file = ProjectDirs::from("github", "the-gorg", "wonky")
-        .context("project directory not found")?
-        .config_dir()
-        .join("config.toml");
-    let buf = std::fs::read(&config_file).with_context(|| {
-        anyhow!("no config file found at: {}", config_file.display())
+/// Everything that can go wrong loading config or reading a widget's value.
+#[derive(Debug)]
+pub enum WonkyError {
+    ConfigNotFound { path: PathBuf },
+    ConfigParse(toml::de::Error),
+    CommandSpawn { cmd: String, source: std::io::Error },
+    OutputNotUtf8 { cmd: String },
+    ValueParse { cmd: String, raw: String },
+    Watch(notify::Error),
+    Io(std::io::Error),
+    PluginProtocol(serde_json::Error),
+    Plugin(&'static str),
+}
+
+impl fmt::Display for WonkyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ConfigNotFound { path } => {
+                write!(f, "no config file found at: {}", path.display())
+            }
+            Self::ConfigParse(e) => write!(f, "invalid config: {}", e),
+            Self::CommandSpawn { cmd, source } => {
+                write!(f, "failed to run `{}`: {}", cmd, source)
+            }
+            Self::OutputNotUtf8 { cmd } => {
+                write!(f, "`{}` produced non-UTF-8 output", cmd)
+            }
+            Self::ValueParse { cmd, raw } => {
+                write!(f, "`{}` produced a non-numeric value: {:?}", cmd, raw)
+            }
+            Self::Watch(e) => write!(f, "config watch failed: {}", e),
+            Self::Io(e) => write!(f, "i/o error: {}", e),
+            Self::PluginProtocol(e) => {
+                write!(f, "malformed plugin message: {}", e)
+            }
+            Self::Plugin(msg) => write!(f, "plugin error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for WonkyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::ConfigParse(e) => Some(e),
+            Self::CommandSpawn { source, .. } => Some(source),
+            Self::Watch(e) => Some(e),
+            Self::Io(e) => Some(e),
+            Self::PluginProtocol(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<toml::de::Error> for WonkyError {
+    fn from(e: toml::de::Error) -> Self {
+        Self::ConfigParse(e)
+    }
+}
+
+impl From<std::io::Error> for WonkyError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for WonkyError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::PluginProtocol(e)
+    }
+}
+
+/// Resolve the path of the `config.toml` wonky reads and watches.
+pub fn config_path() -> Result<PathBuf, WonkyError> {
+    ProjectDirs::from("github", "the-gorg", "wonky")
+        .map(|d| d.config_dir().join("config.toml"))
+        .ok_or_else(|| WonkyError::ConfigNotFound {
+            path: PathBuf::from("config.toml"),
+        })
+}
+
+pub fn load() -> Result<Conf, WonkyError> {
+    let config_file = config_path()?;
+    let buf = std::fs::read(&config_file).map_err(|_| {
+        WonkyError::ConfigNotFound {
+            path: config_file.clone(),
+        }
     })?;
 
-    toml::from_slice(&buf).map_err(Into::into)
+    toml::from_slice(&buf).map_err(WonkyError::ConfigParse)
+}
+
+/// The outcome of re-reading the config after a filesystem change.
+pub type ConfigReload = Result<Conf, WonkyError>;
+
+/// Watch the resolved config file and push a re-parse over `tx` on every
+/// change. The returned watcher must be kept alive for events to keep firing.
+pub fn watch_config(
+    tx: Sender<ConfigReload>,
+) -> Result<notify::RecommendedWatcher, WonkyError> {
+    use notify::{EventKind, RecursiveMode, Watcher};
+
+    let path = config_path()?;
+    let mut watcher = notify::recommended_watcher(
+        move |res: notify::Result<notify::Event>| {
+            if let Ok(event) = res {
+                if matches!(
+                    event.kind,
+                    EventKind::Modify(_) | EventKind::Create(_)
+                ) {
+                    let _ = tx.send_blocking(load());
+                }
+            }
+        },
+    )
+    .map_err(WonkyError::Watch)?;
+
+    watcher
+        .watch(&path, RecursiveMode::NonRecursive)
+        .map_err(WonkyError::Watch)?;
+
+    Ok(watcher)
 }
 
 #[derive(Debug, Deserialize)]
@@ -31,12 +149,158 @@ pub enum Widget {
     Meter(Meter),
     Indicator(Indicator),
     Seperator(Seperator),
+    Plugin(Plugin),
 }
 
 #[derive(Debug, Deserialize)]
 pub struct Conf {
     pub widgets: Vec<Widget>,
     pub settings: Settings,
+
+    /// Surfaced on the bar when a live edit fails to parse; the previous good
+    /// config keeps running until the file is valid again.
+    #[serde(skip_deserializing)]
+    pub last_error: Option<String>,
+
+    /// Handles to the per-widget polling tasks, one per index, so they can be
+    /// aborted and re-keyed when the widget set changes on hot-reload.
+    #[serde(skip_deserializing)]
+    tasks: Vec<tokio::task::JoinHandle<()>>,
+}
+
+/// Identity used to decide whether a reloaded widget is "the same" widget and
+/// may keep its collected state. Matching is on title plus command strings.
+#[derive(Debug, PartialEq, Eq)]
+enum WidgetKey {
+    Meter { title: String, max: String, value: String },
+    Indicator { title: Option<String>, command: String },
+    Plugin { command: String },
+    Seperator { title: Option<String> },
+}
+
+impl Widget {
+    fn key(&self) -> WidgetKey {
+        match self {
+            Widget::Meter(m) => WidgetKey::Meter {
+                title: m.title.clone(),
+                max: m.max_command.clone(),
+                value: m.value_command.clone(),
+            },
+            Widget::Indicator(i) => WidgetKey::Indicator {
+                title: i.title.clone(),
+                command: i.command.clone(),
+            },
+            Widget::Plugin(p) => WidgetKey::Plugin {
+                command: p.command.clone(),
+            },
+            Widget::Seperator(s) => WidgetKey::Seperator {
+                title: s.title.clone(),
+            },
+        }
+    }
+
+    /// Move the runtime state of a matching previous widget into this one so a
+    /// hot-reload doesn't reset gauges or blank the last plugin reading.
+    fn carry_state(&mut self, previous: Widget) {
+        match (self, previous) {
+            (Widget::Meter(new), Widget::Meter(old)) => {
+                new.max_value = old.max_value;
+                new.current_value = old.current_value;
+                new.last_update = old.last_update;
+                new.stale = old.stale;
+                new.samples = old.samples;
+                new.reading_text = old.reading_text;
+            }
+            (Widget::Indicator(new), Widget::Indicator(old)) => {
+                new.value = old.value;
+                new.reading = old.reading;
+                new.last_update = old.last_update;
+                new.stale = old.stale;
+            }
+            (Widget::Plugin(new), Widget::Plugin(old)) => {
+                // The live process lives in the widget's background task, not
+                // the struct, so we only carry the last displayed state; the
+                // re-spawned task re-describes and resumes polling.
+                new.kind = old.kind;
+                new.title = old.title;
+                new.unit = old.unit;
+                new.max_value = old.max_value;
+                new.current_value = old.current_value;
+                new.reading = old.reading;
+                new.stale = old.stale;
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Conf {
+    /// (Re)spawn one background polling task per widget, keyed by its current
+    /// index. Previously spawned tasks are aborted first so that, after the
+    /// widget set changes, no orphaned task keeps routing `WidgetUpdate`s to the
+    /// wrong index.
+    pub fn spawn_all(&mut self, tx: Sender<WidgetUpdate>) {
+        for task in self.tasks.drain(..) {
+            task.abort();
+        }
+
+        let mut tasks = Vec::new();
+        for (id, widget) in self.widgets.iter_mut().enumerate() {
+            match widget {
+                Widget::Meter(meter) => tasks.push(meter.spawn(id, tx.clone())),
+                Widget::Indicator(indicator) => {
+                    tasks.push(indicator.spawn(id, tx.clone()))
+                }
+                Widget::Plugin(plugin) => {
+                    tasks.push(plugin.spawn(id, tx.clone()))
+                }
+                Widget::Seperator(_) => {}
+            }
+        }
+        self.tasks = tasks;
+    }
+
+    /// Apply a reloaded config in place. Widgets whose title and command
+    /// strings are unchanged keep their collected state; new or modified meters
+    /// are re-initialised, and every polling task is respawned. A failed parse
+    /// leaves the running config untouched and records the error for display.
+    pub fn reconcile(
+        &mut self,
+        incoming: ConfigReload,
+        tx: Sender<WidgetUpdate>,
+    ) {
+        let mut new = match incoming {
+            Ok(conf) => conf,
+            Err(e) => {
+                self.last_error = Some(e.to_string());
+                return;
+            }
+        };
+
+        let mut old = std::mem::take(&mut self.widgets);
+        let mut error = None;
+
+        for widget in &mut new.widgets {
+            let key = widget.key();
+            if let Some(idx) = old.iter().position(|w| w.key() == key) {
+                widget.carry_state(old.remove(idx));
+            } else if let Widget::Meter(meter) = widget {
+                // New or modified meters need their max value re-established;
+                // plugins re-describe from within their own task on spawn.
+                if let Err(e) = meter.init() {
+                    error = Some(e.to_string());
+                }
+            }
+        }
+
+        self.widgets = new.widgets;
+        self.settings = new.settings;
+        self.last_error = error;
+
+        // Indices may have shifted; abort the old tasks and re-key fresh ones so
+        // every widget — carried-over, new, or moved — polls under its new id.
+        self.spawn_all(tx);
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -54,6 +318,10 @@ pub struct Indicator {
     command: String,
     frequency: u64,
 
+    /// Where the value comes from; defaults to running `command`.
+    #[serde(default)]
+    source: Option<Source>,
+
     pub right: bool,
     pub bottom: bool,
 
@@ -61,35 +329,101 @@ pub struct Indicator {
     value: bool,
     #[serde(skip_deserializing)]
     reading: String,
+
+    #[serde(skip_deserializing)]
+    pub stale: bool,
     #[serde(skip_deserializing)]
-    timer: Option<Instant>,
+    last_update: Option<Instant>,
 }
 
 impl Indicator {
-    pub fn update(&mut self) -> Result<()> {
-        if self
-            .timer
-            .map(|t| t.elapsed().as_secs() > self.frequency)
-            .unwrap_or(true)
-        {
-            self.timer = Some(Instant::now());
-
-            if let Some(mut cmd) = construct_command(&self.command) {
-                self.value = cmd.get_stdout().parse()?;
+    /// The resolved value source, defaulting to the legacy `command`.
+    fn source(&self) -> Source {
+        self.source.clone().unwrap_or_else(|| Source::Command {
+            command: self.command.clone(),
+        })
+    }
+
+    /// Spawn a background task polling the source every `frequency` seconds.
+    pub fn spawn(
+        &mut self,
+        id: usize,
+        tx: Sender<WidgetUpdate>,
+    ) -> tokio::task::JoinHandle<()> {
+        let source = self.source();
+        let frequency = self.frequency;
+        // Seed the freshness clock at spawn so a first poll that never returns
+        // flips the widget stale once its interval elapses.
+        self.last_update = Some(Instant::now());
+
+        tokio::spawn(async move {
+            loop {
+                let sample = match source.read().await {
+                    // Textual sources (clock) are not parsed; their text
+                    // becomes the reading.
+                    Some(raw) if source.is_textual() => {
+                        Some(Sample::Reading { reading: raw })
+                    }
+                    Some(output) => {
+                        let mut split = output.split(' ');
+                        split.next().and_then(|v| v.parse().ok()).map(
+                            |value| Sample::Indicator {
+                                value,
+                                reading: split.collect(),
+                            },
+                        )
+                    }
+                    None => None,
+                };
+
+                if let Some(sample) = sample {
+                    if tx.send(WidgetUpdate { id, sample }).await.is_err() {
+                        break;
+                    }
+                }
+
+                tokio::time::sleep(Duration::from_secs(frequency)).await;
             }
-        }
+        })
+    }
 
-        Ok(())
+    /// Apply a value drained from the update channel.
+    pub fn apply(&mut self, value: bool, reading: String) {
+        self.value = value;
+        self.reading = reading;
+        self.last_update = Some(Instant::now());
+        self.stale = false;
+    }
+
+    /// Apply a textual reading (e.g. from a `clock` source): it is stored as the
+    /// reading and the indicator is switched on. [`Indicator::draw`] shows only
+    /// the title, not the reading.
+    pub fn apply_reading(&mut self, reading: String) {
+        self.reading = reading;
+        self.value = true;
+        self.last_update = Some(Instant::now());
+        self.stale = false;
+    }
+
+    /// A poll is overdue when no fresh value has arrived within its interval.
+    pub fn refresh_stale(&mut self) {
+        if let Some(last) = self.last_update {
+            self.stale = last.elapsed().as_secs() > self.frequency;
+        }
     }
 
-    pub fn init(&mut self) -> Result<()> {
-        if let Some(output) =
-            construct_command(&self.command).map(|mut cmd| cmd.get_stdout())
-        {
+    pub fn init(&mut self) -> Result<(), WonkyError> {
+        if let Some(mut cmd) = construct_command(&self.command) {
+            let output = cmd.get_stdout()?;
             let mut split = output.split(' ');
 
             if let Some(value) = split.next() {
-                self.value = value.parse()?;
+                self.value = value.parse().map_err(|_| {
+                    WonkyError::ValueParse {
+                        cmd: self.command.clone(),
+                        raw: value.to_string(),
+                    }
+                })?;
                 self.reading = split.collect();
             }
         }
@@ -98,6 +432,244 @@ impl Indicator {
     }
 }
 
+//-------------------------------------------------------------------------------------
+// Plugins
+//-------------------------------------------------------------------------------------
+
+/// Which kind of widget a plugin declares itself to be when `describe`d.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginKind {
+    Meter,
+    Indicator,
+}
+
+/// A request sent to a plugin over its stdin as a single JSON line.
+#[derive(Debug, Serialize)]
+#[serde(tag = "method", rename_all = "lowercase")]
+enum PluginRequest {
+    Describe,
+    Poll,
+}
+
+/// The reply to a `describe` request, declaring the plugin's shape.
+#[derive(Debug, Deserialize)]
+struct PluginDescribe {
+    kind: PluginKind,
+    title: String,
+    unit: String,
+    max: u64,
+}
+
+/// The reply to a `poll` request, carrying the current reading.
+#[derive(Debug, Deserialize)]
+struct PluginPoll {
+    value: u64,
+    reading: String,
+}
+
+/// Timeout for a single plugin request so a hung plugin can't wedge polling.
+const PLUGIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A spawned plugin process with its pipes held open for the whole run. All of
+/// its I/O is async so it only ever blocks its own background task.
+struct PluginProcess {
+    _child: tokio::process::Child,
+    stdin: tokio::process::ChildStdin,
+    stdout: tokio::io::BufReader<tokio::process::ChildStdout>,
+}
+
+impl PluginProcess {
+    /// Spawn the executable with its stdio piped, killing it on drop.
+    async fn start(command: &str) -> Result<Self, WonkyError> {
+        let mut child = construct_async_command(command)
+            .ok_or(WonkyError::Plugin("empty plugin command"))?
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or(WonkyError::Plugin("plugin stdin unavailable"))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or(WonkyError::Plugin("plugin stdout unavailable"))?;
+
+        Ok(Self {
+            _child: child,
+            stdin,
+            stdout: tokio::io::BufReader::new(stdout),
+        })
+    }
+
+    /// Send a request as one JSON line and read one JSON line back, giving up
+    /// after `PLUGIN_TIMEOUT` so a stalled plugin surfaces as an error instead
+    /// of hanging the poll loop.
+    async fn request<T: for<'de> Deserialize<'de>>(
+        &mut self,
+        req: &PluginRequest,
+    ) -> Result<T, WonkyError> {
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+
+        let mut line = serde_json::to_string(req)?;
+        line.push('\n');
+        self.stdin.write_all(line.as_bytes()).await?;
+        self.stdin.flush().await?;
+
+        let mut resp = String::new();
+        let read =
+            tokio::time::timeout(PLUGIN_TIMEOUT, self.stdout.read_line(&mut resp))
+                .await
+                .map_err(|_| WonkyError::Plugin("plugin timed out"))??;
+
+        if read == 0 {
+            return Err(WonkyError::Plugin("plugin closed its output stream"));
+        }
+
+        serde_json::from_str(resp.trim()).map_err(Into::into)
+    }
+}
+
+/// A long-lived collector spoken to over newline-delimited JSON-RPC.
+///
+/// The executable is spawned once and kept resident, avoiding the fork/exec
+/// churn of the one-shot command widgets for cheap or stateful metrics.
+#[derive(Debug, Deserialize)]
+pub struct Plugin {
+    command: String,
+    frequency: u64,
+
+    pub right: bool,
+    pub bottom: bool,
+
+    #[serde(skip_deserializing)]
+    pub title: String,
+    #[serde(skip_deserializing)]
+    pub unit: String,
+    #[serde(skip_deserializing)]
+    pub kind: Option<PluginKind>,
+    #[serde(skip_deserializing)]
+    pub max_value: u64,
+    #[serde(skip_deserializing)]
+    pub current_value: u64,
+    #[serde(skip_deserializing)]
+    pub reading: String,
+
+    #[serde(skip_deserializing)]
+    stale: bool,
+}
+
+impl Plugin {
+    /// Whether the backing process has died and the widget should be dimmed.
+    pub fn is_stale(&self) -> bool {
+        self.stale
+    }
+
+    /// Spawn a background task that owns the process, performs the `describe`
+    /// handshake, then polls it every `frequency` seconds. All I/O happens off
+    /// the render thread, so a slow or hung plugin can never freeze the UI.
+    pub fn spawn(
+        &self,
+        id: usize,
+        tx: Sender<WidgetUpdate>,
+    ) -> tokio::task::JoinHandle<()> {
+        let command = self.command.clone();
+        let frequency = self.frequency;
+
+        tokio::spawn(async move {
+            let mut process = match PluginProcess::start(&command).await {
+                Ok(process) => process,
+                Err(_) => {
+                    let _ = tx
+                        .send(WidgetUpdate { id, sample: Sample::Stale })
+                        .await;
+                    return;
+                }
+            };
+
+            match process
+                .request::<PluginDescribe>(&PluginRequest::Describe)
+                .await
+            {
+                Ok(d) => {
+                    let sample = Sample::PluginDescribe {
+                        kind: d.kind,
+                        title: d.title,
+                        unit: d.unit,
+                        max: d.max,
+                    };
+                    if tx.send(WidgetUpdate { id, sample }).await.is_err() {
+                        return;
+                    }
+                }
+                Err(_) => {
+                    let _ = tx
+                        .send(WidgetUpdate { id, sample: Sample::Stale })
+                        .await;
+                    return;
+                }
+            }
+
+            loop {
+                match process
+                    .request::<PluginPoll>(&PluginRequest::Poll)
+                    .await
+                {
+                    Ok(poll) => {
+                        let sample = Sample::Plugin {
+                            value: poll.value,
+                            reading: poll.reading,
+                        };
+                        if tx.send(WidgetUpdate { id, sample }).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(_) => {
+                        // Hung, dead, or garbage: keep the last reading on
+                        // screen, flagged stale, and stop the task.
+                        let _ = tx
+                            .send(WidgetUpdate { id, sample: Sample::Stale })
+                            .await;
+                        break;
+                    }
+                }
+
+                tokio::time::sleep(Duration::from_secs(frequency)).await;
+            }
+        })
+    }
+
+    /// Apply the plugin's self-description from the `describe` handshake.
+    pub fn apply_describe(
+        &mut self,
+        kind: PluginKind,
+        title: String,
+        unit: String,
+        max: u64,
+    ) {
+        self.kind = Some(kind);
+        self.title = title;
+        self.unit = unit;
+        self.max_value = max;
+        self.stale = false;
+    }
+
+    /// Apply a poll result drained from the update channel.
+    pub fn apply_poll(&mut self, value: u64, reading: String) {
+        self.current_value = value;
+        self.reading = reading;
+        self.stale = false;
+    }
+
+    /// Flag the plugin stale after its task reported a failure.
+    pub fn mark_stale(&mut self) {
+        self.stale = true;
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Meter {
     pub title: String,
@@ -108,12 +680,23 @@ pub struct Meter {
     value_command: String,
     frequency: u64,
 
+    /// Where the current value comes from; defaults to `value_command`.
+    #[serde(default)]
+    source: Option<Source>,
+
     pub right: bool,
     pub bottom: bool,
 
     pub meter: bool,
     pub reading: bool,
 
+    /// Render the rolling history as a sparkline instead of the gauge.
+    #[serde(default)]
+    pub graph: bool,
+    /// How many samples the rolling history keeps.
+    #[serde(default = "default_history")]
+    history: usize,
+
     pub theme: usize,
 
     #[serde(skip_deserializing)]
@@ -125,22 +708,46 @@ pub struct Meter {
     max_cmd: Option<Command>,
     #[serde(skip_deserializing)]
     value_cmd: Option<Command>,
+
+    #[serde(skip_deserializing)]
+    pub stale: bool,
+    #[serde(skip_deserializing)]
+    last_update: Option<Instant>,
+
+    #[serde(skip_deserializing)]
+    samples: VecDeque<u64>,
+
+    /// Display string for textual sources (e.g. `clock`); when set it is shown
+    /// in place of the `value/max` readout.
     #[serde(skip_deserializing)]
-    timer: Option<Instant>,
+    reading_text: Option<String>,
+}
+
+/// Default number of samples kept in a meter's rolling history.
+fn default_history() -> usize {
+    60
 }
 
 pub trait CommandExt {
-    fn get_stdout(&mut self) -> String;
+    fn get_stdout(&mut self) -> Result<String, WonkyError>;
 }
 
 impl CommandExt for Command {
-    fn get_stdout(&mut self) -> String {
-        let output = self.output().expect("oops").stdout;
+    fn get_stdout(&mut self) -> Result<String, WonkyError> {
+        let cmd = self.get_program().to_string_lossy().into_owned();
+
+        let output = self
+            .output()
+            .map_err(|source| WonkyError::CommandSpawn {
+                cmd: cmd.clone(),
+                source,
+            })?
+            .stdout;
+
+        let text = std::str::from_utf8(&output)
+            .map_err(|_| WonkyError::OutputNotUtf8 { cmd })?;
 
-        std::str::from_utf8(&output)
-            .expect("berp")
-            .trim()
-            .to_string()
+        Ok(text.trim().to_string())
     }
 }
 
@@ -153,8 +760,8 @@ impl Default for Meter {
             current_value: 0,
             max_command: "echo 16014".to_string(),
             value_command: "memcheck".to_string(),
+            source: None,
             frequency: 1,
-            timer: None,
             value_cmd: construct_command("memcheck"),
             max_cmd: construct_command("echo 16000"),
             prefix: None,
@@ -163,30 +770,61 @@ impl Default for Meter {
             bottom: false,
             meter: true,
             reading: true,
+            graph: false,
+            history: default_history(),
+            stale: false,
+            last_update: None,
+            samples: VecDeque::new(),
+            reading_text: None,
         }
     }
 }
 
 impl Meter {
-    pub fn update(&mut self) -> Result<()> {
-        if self
-            .timer
-            .map(|t| t.elapsed().as_secs() > self.frequency)
-            .unwrap_or(true)
-        {
-            self.timer = Some(Instant::now());
-
-            if let Some(mut cmd) = construct_command(&self.value_command) {
-                self.current_value = cmd.get_stdout().parse()?;
+    /// Push the current value onto the rolling history, evicting the oldest
+    /// sample once the configured capacity is reached.
+    fn record_sample(&mut self) {
+        if self.samples.len() >= self.history {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(self.current_value);
+    }
+
+    /// Render the last `width` samples as a block-glyph sparkline, newest on
+    /// the right and blank columns where no sample exists yet.
+    fn sparkline(&self, width: usize) -> String {
+        const GLYPHS: [char; 8] =
+            ['\u{2581}', '\u{2582}', '\u{2583}', '\u{2584}', '\u{2585}',
+             '\u{2586}', '\u{2587}', '\u{2588}'];
+
+        let start = self.samples.len().saturating_sub(width);
+        let visible = self.samples.iter().skip(start);
+
+        let mut out =
+            " ".repeat(width.saturating_sub(self.samples.len()));
+        for &sample in visible {
+            if self.max_value == 0 {
+                out.push(GLYPHS[0]);
+                continue;
             }
+
+            let ratio =
+                (sample as f32 / self.max_value as f32).clamp(0.0, 1.0);
+            let idx = (ratio * (GLYPHS.len() - 1) as f32).round() as usize;
+            out.push(GLYPHS[idx.min(GLYPHS.len() - 1)]);
         }
 
-        Ok(())
+        out
     }
 
-    pub fn init(&mut self) -> Result<()> {
+    pub fn init(&mut self) -> Result<(), WonkyError> {
         if let Some(mut cmd) = construct_command(&self.max_command) {
-            self.max_value = cmd.get_stdout().parse()?;
+            let raw = cmd.get_stdout()?;
+            self.max_value =
+                raw.parse().map_err(|_| WonkyError::ValueParse {
+                    cmd: self.max_command.clone(),
+                    raw,
+                })?;
         }
 
         Ok(())
@@ -195,77 +833,220 @@ impl Meter {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// The resolved value source, defaulting to the legacy `value_command`.
+    fn value_source(&self) -> Source {
+        self.source.clone().unwrap_or_else(|| Source::Command {
+            command: self.value_command.clone(),
+        })
+    }
+
+    /// Spawn a background task that polls the source every `frequency`
+    /// seconds and pushes the parsed value back over `tx`, never blocking the
+    /// render thread.
+    pub fn spawn(
+        &mut self,
+        id: usize,
+        tx: Sender<WidgetUpdate>,
+    ) -> tokio::task::JoinHandle<()> {
+        let source = self.value_source();
+        let frequency = self.frequency;
+        // Seed the freshness clock at spawn so a first poll that never returns
+        // flips the widget stale once its interval elapses.
+        self.last_update = Some(Instant::now());
+
+        tokio::spawn(async move {
+            loop {
+                let sample = match source.read().await {
+                    // Textual sources (clock) feed the display, not the gauge.
+                    Some(raw) if source.is_textual() => {
+                        Some(Sample::Reading { reading: raw })
+                    }
+                    Some(raw) => {
+                        raw.parse().ok().map(|value| Sample::Meter { value })
+                    }
+                    None => None,
+                };
+
+                if let Some(sample) = sample {
+                    if tx.send(WidgetUpdate { id, sample }).await.is_err() {
+                        break;
+                    }
+                }
+
+                tokio::time::sleep(Duration::from_secs(frequency)).await;
+            }
+        })
+    }
+
+    /// Apply a value drained from the update channel.
+    pub fn apply(&mut self, value: u64) {
+        self.current_value = value;
+        self.record_sample();
+        self.last_update = Some(Instant::now());
+        self.stale = false;
+    }
+
+    /// Apply a textual reading (e.g. from a `clock` source) for display.
+    pub fn apply_reading(&mut self, reading: String) {
+        self.reading_text = Some(reading);
+        self.last_update = Some(Instant::now());
+        self.stale = false;
+    }
+
+    /// A poll is overdue when no fresh value has arrived within its interval.
+    pub fn refresh_stale(&mut self) {
+        if let Some(last) = self.last_update {
+            self.stale = last.elapsed().as_secs() > self.frequency;
+        }
+    }
 }
 
 //-------------------------------------------------------------------------------------
 // Drawing
 //-------------------------------------------------------------------------------------
+
+/// A cell target a widget draws its text into. Abstracting the real
+/// [`Viewport`] behind this trait keeps each widget's drawing a pure function of
+/// its state, so the snapshot tests can render into an in-memory grid and read
+/// the cells back while production still renders straight to the terminal.
+pub trait Surface {
+    /// The target's size in cells.
+    fn size(&self) -> Size;
+
+    /// Draw `text` starting at `pos`, tinting every written cell with the given
+    /// foreground and background colours.
+    fn put(
+        &mut self,
+        text: &str,
+        fg: Option<Color>,
+        bg: Option<Color>,
+        pos: ScreenPos,
+    );
+}
+
+impl Surface for Viewport {
+    fn size(&self) -> Size {
+        self.size
+    }
+
+    fn put(
+        &mut self,
+        text: &str,
+        fg: Option<Color>,
+        bg: Option<Color>,
+        pos: ScreenPos,
+    ) {
+        self.draw_widget(&Text::new(text.to_string(), fg, bg), pos);
+    }
+}
+
 impl Meter {
     pub fn update_and_draw(
         &mut self,
         viewport: &mut Viewport,
         pos: &mut ScreenPos,
         theme: &MeterTheme,
-    ) -> Result<()> {
-        self.update()?;
+    ) -> Result<(), WonkyError> {
+        // Polling happens in a background task; here we only reflect the last
+        // known value and flag it stale if a fresh sample is overdue.
+        self.refresh_stale();
+        self.draw(viewport, pos, theme);
+
+        Ok(())
+    }
+
+    /// The formatted value readout, e.g. `4096/16014mb`.
+    fn reading(&self) -> String {
+        format!("{}/{}{}", self.current_value, self.max_value, self.unit)
+    }
+
+    /// The column at which the right-aligned reading starts within the given
+    /// viewport width. The margin keeps it clear of the meter's end cap.
+    fn reading_x(&self, origin: u16, viewport_width: u16, reading: &str) -> u16 {
+        origin
+            + (viewport_width / 2 - READING_MARGIN - reading.len() as u16)
+    }
+
+    /// Draw the meter's title, reading and — in graph mode — its sparkline into
+    /// any surface. Pure in the widget's state, which is what the snapshot tests
+    /// render against; the gauge body is the theme's job (see [`Meter::draw`]).
+    fn render(&self, surface: &mut impl Surface, pos: &ScreenPos) {
+        let value_color = if self.stale { bg_color() } else { fg_color() };
+        let width = surface.size().width;
 
-        viewport.draw_widget(
-            &Text::new(self.title.clone(), fg_color(), None),
+        surface.put(
+            &self.title,
+            value_color,
+            None,
             ScreenPos::new(pos.x, pos.y),
         );
 
         if self.reading {
-            let value_reading = Text::new(
-                format!(
-                    "{}/{}{}",
-                    self.current_value, self.max_value, self.unit
-                ),
-                fg_color(),
+            let reading = self
+                .reading_text
+                .clone()
+                .unwrap_or_else(|| self.reading());
+            let x = self.reading_x(pos.x, width, &reading);
+            surface.put(
+                &reading,
+                value_color,
                 None,
-            );
-
-            viewport.draw_widget(
-                &value_reading,
-                ScreenPos::new(
-                    // TODO: why 2?!?
-                    pos.x
-                        + (viewport.size.width / 2
-                            - 2
-                            - value_reading.0.len() as u16),
-                    pos.y.saturating_sub(1),
-                ),
+                ScreenPos::new(x, pos.y.saturating_sub(1)),
             );
         }
-        if self.title != "" {
-            viewport.draw_widget(
-                &Text::new(self.title.clone(), fg_color(), None),
+        if !self.title.is_empty() {
+            surface.put(
+                &self.title,
+                fg_color(),
+                None,
                 ScreenPos::new(pos.x, pos.y.saturating_sub(1)),
             );
         }
 
-        theme.draw(
-            viewport,
-            self,
-            (self.current_value as f32, self.max_value as f32),
-            ScreenPos::new(pos.x, pos.y),
-        );
+        if self.graph {
+            surface.put(
+                &self.sparkline((width / 2) as usize),
+                value_color,
+                None,
+                ScreenPos::new(pos.x, pos.y),
+            );
+        }
+    }
 
-        Ok(())
+    /// Draw the meter to the terminal viewport, delegating the gauge body to the
+    /// configured [`MeterTheme`] when not rendering a sparkline.
+    pub fn draw(
+        &self,
+        viewport: &mut Viewport,
+        pos: &ScreenPos,
+        theme: &MeterTheme,
+    ) {
+        self.render(viewport, pos);
+
+        if !self.graph {
+            theme.draw(
+                viewport,
+                self,
+                (self.current_value as f32, self.max_value as f32),
+                ScreenPos::new(pos.x, pos.y),
+            );
+        }
     }
 }
 
+/// Gap kept between the right-aligned reading and the meter's end cap.
+const READING_MARGIN: u16 = 2;
+
 impl Seperator {
     //
     pub fn draw(
-        &mut self,
-        viewport: &mut Viewport,
-        pos: &mut ScreenPos,
-    ) -> Result<()> {
+        &self,
+        surface: &mut impl Surface,
+        pos: &ScreenPos,
+    ) -> Result<(), WonkyError> {
         if let Some(t) = &self.title {
-            viewport.draw_widget(
-                &Text::new(t, fg_color(), None),
-                ScreenPos::new(pos.x, pos.y),
-            );
+            surface.put(t, fg_color(), None, ScreenPos::new(pos.x, pos.y));
         }
 
         Ok(())
@@ -278,30 +1059,32 @@ impl Indicator {
         &mut self,
         viewport: &mut Viewport,
         pos: &mut ScreenPos,
-    ) -> Result<()> {
-        self.update()?;
+    ) -> Result<(), WonkyError> {
+        // Values arrive from a background task; drawing never blocks on a poll.
+        self.refresh_stale();
+        self.draw(viewport, pos);
+
+        Ok(())
+    }
+
+    /// Draw the indicator purely from its current state.
+    pub fn draw(&self, surface: &mut impl Surface, pos: &ScreenPos) {
         let colors = match self.value {
             true => (Some(Color::Black), fg_color()),
             false => (Some(Color::Black), bg_color()),
         };
+        let width = surface.size().width;
 
-        viewport.draw_widget(
-            &Text::new(
-                " ".repeat((viewport.size.width / 2 - 2) as usize),
-                None,
-                colors.1,
-            ),
+        surface.put(
+            &" ".repeat((width / 2 - 2) as usize),
+            None,
+            colors.1,
             *pos,
         );
 
         if let Some(t) = &self.title {
-            viewport.draw_widget(
-                &Text::new(t, colors.0, colors.1),
-                ScreenPos::new(pos.x, pos.y),
-            );
+            surface.put(t, colors.0, colors.1, ScreenPos::new(pos.x, pos.y));
         }
-
-        Ok(())
     }
 }
 
@@ -319,6 +1102,116 @@ fn construct_command(command: &str) -> Option<Command> {
     Some(command)
 }
 
+/// Build an async [`tokio::process::Command`] from a shell-style string.
+fn construct_async_command(command: &str) -> Option<tokio::process::Command> {
+    let mut split = command.split_whitespace();
+    let cmd = split.next()?;
+
+    let mut command = tokio::process::Command::new(cmd);
+    command.args(split);
+
+    Some(command)
+}
+
+/// A single polled sample produced by a widget's background task.
+#[derive(Debug)]
+pub enum Sample {
+    Meter { value: u64 },
+    Indicator { value: bool, reading: String },
+    /// A textual reading (e.g. from a `clock` source) for the display field,
+    /// routed around the numeric value path.
+    Reading { reading: String },
+    /// A plugin's reply to the initial `describe` handshake.
+    PluginDescribe {
+        kind: PluginKind,
+        title: String,
+        unit: String,
+        max: u64,
+    },
+    /// A plugin's reply to a `poll` request.
+    Plugin { value: u64, reading: String },
+    /// The widget's task failed or its plugin stalled; dim it.
+    Stale,
+}
+
+/// A background poll result routed back to the widget by its index.
+#[derive(Debug)]
+pub struct WidgetUpdate {
+    pub id: usize,
+    pub sample: Sample,
+}
+
+/// Run a command once and return its trimmed stdout, asynchronously.
+async fn poll_stdout(command: &str) -> Option<String> {
+    let output = construct_async_command(command)?.output().await.ok()?;
+    let text = std::str::from_utf8(&output.stdout).ok()?;
+    Some(text.trim().to_string())
+}
+
+/// Where a widget reads its value from. Defaults to `command` so existing
+/// configs keep working unchanged.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Source {
+    /// Spawn a shell command and read its stdout (the historical behaviour).
+    Command { command: String },
+    /// Re-read a file each tick, e.g. a sysfs/procfs node, with no fork.
+    File { path: PathBuf },
+    /// Produce the current time formatted with a `strftime`-style pattern.
+    Clock { format: String },
+    /// Pick up the latest line fed on wonky's own stdin under `label`.
+    Stdin { label: String },
+}
+
+impl Source {
+    /// Whether this source yields a display string rather than a number, so it
+    /// must be routed to the reading field instead of being parsed as a value.
+    fn is_textual(&self) -> bool {
+        matches!(self, Source::Clock { .. })
+    }
+
+    /// Read the current raw value without blocking the render thread.
+    async fn read(&self) -> Option<String> {
+        match self {
+            Source::Command { command } => poll_stdout(command).await,
+            Source::File { path } => tokio::fs::read_to_string(path)
+                .await
+                .ok()
+                .map(|s| s.trim().to_string()),
+            Source::Clock { format } => {
+                Some(chrono::Local::now().format(format).to_string())
+            }
+            Source::Stdin { label } => {
+                stdin_values().lock().unwrap().get(label).cloned()
+            }
+        }
+    }
+}
+
+/// Latest value seen per label on wonky's stdin, for `Source::Stdin` widgets.
+fn stdin_values() -> &'static Mutex<HashMap<String, String>> {
+    static VALUES: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+    VALUES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Spawn a task reading `label value` lines from wonky's own stdin, keeping the
+/// most recent value per label for `Source::Stdin` widgets to pick up.
+pub fn spawn_stdin_reader() {
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if let Some((label, value)) = line.split_once(' ') {
+                stdin_values()
+                    .lock()
+                    .unwrap()
+                    .insert(label.to_string(), value.trim().to_string());
+            }
+        }
+    });
+}
+
 #[allow(dead_code, clippy::unnecessary_wraps)]
 fn fg_color() -> Option<Color> {
     Some(Color::Green)
@@ -328,3 +1221,203 @@ fn fg_color() -> Option<Color> {
 fn bg_color() -> Option<Color> {
     Some(Color::DarkGreen)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An in-memory [`Surface`] that records the glyph and colours written to
+    /// every cell, so a widget's drawing can be rendered and read back without a
+    /// terminal. `serialize` turns the grid into a stable text form — a glyph
+    /// block followed by foreground and background colour blocks — that the
+    /// snapshot tests assert against.
+    ///
+    /// The gauge body drawn by `MeterTheme` is out of scope here: the theme
+    /// renders straight to the real `Viewport` and lives outside this module, so
+    /// the snapshots cover each widget's own (theme-independent) output.
+    struct Grid {
+        width: u16,
+        height: u16,
+        cells: Vec<(char, Option<Color>, Option<Color>)>,
+    }
+
+    impl Grid {
+        fn new(width: u16, height: u16) -> Self {
+            Self {
+                width,
+                height,
+                cells: vec![
+                    (' ', None, None);
+                    width as usize * height as usize
+                ],
+            }
+        }
+
+        fn cell(&self, x: u16, y: u16) -> &(char, Option<Color>, Option<Color>) {
+            &self.cells[y as usize * self.width as usize + x as usize]
+        }
+
+        fn serialize(&self) -> String {
+            let mut out = String::new();
+            for y in 0..self.height {
+                let row: String =
+                    (0..self.width).map(|x| self.cell(x, y).0).collect();
+                out.push_str(row.trim_end_matches(' '));
+                out.push('\n');
+            }
+            out.push_str("fg:\n");
+            self.push_colors(&mut out, |c| c.1);
+            out.push_str("bg:\n");
+            self.push_colors(&mut out, |c| c.2);
+            out
+        }
+
+        fn push_colors(
+            &self,
+            out: &mut String,
+            pick: impl Fn(&(char, Option<Color>, Option<Color>)) -> Option<Color>,
+        ) {
+            for y in 0..self.height {
+                let row: String = (0..self.width)
+                    .map(|x| color_code(pick(self.cell(x, y))))
+                    .collect();
+                out.push_str(row.trim_end_matches('.'));
+                out.push('\n');
+            }
+        }
+    }
+
+    /// A single, stable character per colour, with `.` standing for "unset".
+    fn color_code(color: Option<Color>) -> char {
+        match color {
+            None => '.',
+            Some(Color::Green) => 'g',
+            Some(Color::DarkGreen) => 'G',
+            Some(Color::Black) => 'k',
+            Some(_) => '?',
+        }
+    }
+
+    impl Surface for Grid {
+        fn size(&self) -> Size {
+            Size {
+                width: self.width,
+                height: self.height,
+            }
+        }
+
+        fn put(
+            &mut self,
+            text: &str,
+            fg: Option<Color>,
+            bg: Option<Color>,
+            pos: ScreenPos,
+        ) {
+            if pos.y >= self.height {
+                return;
+            }
+            for (i, ch) in text.chars().enumerate() {
+                let x = pos.x + i as u16;
+                if x >= self.width {
+                    break;
+                }
+                let idx = pos.y as usize * self.width as usize + x as usize;
+                self.cells[idx] = (ch, fg, bg);
+            }
+        }
+    }
+
+    fn sample_meter() -> Meter {
+        let mut meter = Meter::new();
+        meter.title = "RAM".to_string();
+        meter.unit = "mb".to_string();
+        meter.current_value = 4096;
+        meter.max_value = 16014;
+        meter
+    }
+
+    #[test]
+    fn reading_is_formatted_value_slash_max_unit() {
+        assert_eq!(sample_meter().reading(), "4096/16014mb");
+    }
+
+    #[test]
+    fn reading_is_right_aligned_with_end_cap_margin() {
+        let meter = sample_meter();
+        let reading = meter.reading();
+        // Half of a width-40 viewport, less the end-cap margin, less the text.
+        assert_eq!(
+            meter.reading_x(0, 40, &reading),
+            20 - READING_MARGIN - reading.len() as u16,
+        );
+    }
+
+    #[test]
+    fn sparkline_right_aligns_newest_and_pads_missing_columns() {
+        let mut meter = sample_meter();
+        meter.max_value = 8;
+        meter.samples = VecDeque::from(vec![0, 4, 8]);
+        assert_eq!(meter.sparkline(5), "  \u{2581}\u{2585}\u{2588}");
+    }
+
+    #[test]
+    fn sparkline_empty_history_is_all_blanks() {
+        assert_eq!(sample_meter().sparkline(4), "    ");
+    }
+
+    #[test]
+    fn meter_renders_title_and_right_aligned_reading() {
+        let mut meter = sample_meter();
+        meter.title = "CPU".to_string();
+        meter.unit = "%".to_string();
+        meter.current_value = 40;
+        meter.max_value = 99;
+        meter.reading = true;
+
+        let mut grid = Grid::new(24, 2);
+        meter.render(&mut grid, &ScreenPos::new(0, 1));
+
+        assert_eq!(
+            grid.serialize(),
+            "CPU 40/99%\nCPU\nfg:\nggg.gggggg\nggg\nbg:\n\n\n",
+        );
+    }
+
+    #[test]
+    fn indicator_on_fills_row_and_labels_it() {
+        let indicator = Indicator {
+            title: Some("net".to_string()),
+            command: "ping".to_string(),
+            frequency: 1,
+            source: None,
+            right: false,
+            bottom: false,
+            value: true,
+            reading: String::new(),
+            stale: false,
+            last_update: None,
+        };
+
+        let mut grid = Grid::new(24, 1);
+        indicator.draw(&mut grid, &ScreenPos::new(0, 0));
+
+        assert_eq!(
+            grid.serialize(),
+            "net\nfg:\nkkk\nbg:\ngggggggggg\n",
+        );
+    }
+
+    #[test]
+    fn seperator_draws_its_title() {
+        let seperator = Seperator {
+            title: Some("sep".to_string()),
+            right: false,
+            bottom: false,
+        };
+
+        let mut grid = Grid::new(24, 1);
+        seperator.draw(&mut grid, &ScreenPos::new(0, 0)).unwrap();
+
+        assert_eq!(grid.serialize(), "sep\nfg:\nggg\nbg:\n\n");
+    }
+}